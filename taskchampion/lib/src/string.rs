@@ -1,6 +1,7 @@
 use crate::traits::*;
 use ffizz_passby::OpaqueStruct;
 use ffizz_string::FzString;
+use std::ffi::CString;
 
 #[ffizz_header::item]
 #[ffizz(order = 200)]
@@ -58,7 +59,10 @@ pub use ffizz_string::fz_string_t as TCString;
 ///
 /// TCStringList represents a list of strings.
 ///
-/// The content of this struct must be treated as read-only.
+/// Most functions that return a TCStringList document it as read-only. The exception is a list
+/// returned by `tc_string_list_new`, which may be modified in place with `tc_string_list_push`
+/// and `tc_string_list_clear`; its content must still be treated as read-only for any other
+/// purpose (for example, do not write directly into `items`).
 ///
 /// ```c
 /// typedef struct TCStringList {
@@ -200,6 +204,52 @@ pub unsafe extern "C" fn tc_string_clone_with_len(
     ::ffizz_string::fz_string_clone_with_len(cstr, size)
 }
 
+// The interior-NUL scan below depends on the `memchr` crate. As elsewhere in this tree, there is
+// no Cargo.toml anywhere in this snapshot to add that dependency to; it needs to land in the real
+// project's taskchampion-lib manifest.
+
+#[ffizz_header::item]
+#[ffizz(order = 202)]
+/// Create a new `TCString` by cloning the given buffer, rejecting the input if it contains an
+/// interior NUL.  This mirrors the `CString::new`/`NulError` pattern: on success, a validated
+/// `TCString` is returned; on failure, the Null variant is returned and, if `nul_pos_out` is not
+/// NULL, the byte offset of the first interior NUL is written to it.
+///
+/// The given length should _not_ include any NUL terminator.
+///
+/// # Safety
+///
+/// The given pointer must not be NULL and must be valid for `len` bytes.
+///
+/// ```c
+/// EXTERN_C TCString tc_string_clone_checked(const char *ptr, size_t len, size_t *nul_pos_out);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_clone_checked(
+    ptr: *const i8,
+    len: usize,
+    nul_pos_out: *mut usize,
+) -> ::ffizz_string::fz_string_t {
+    // SAFETY: ptr is valid for len bytes (promised by caller)
+    let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len) };
+    match memchr::memchr(0, bytes) {
+        Some(pos) => {
+            if !nul_pos_out.is_null() {
+                // SAFETY: nul_pos_out is a valid, properly aligned out-param (promised by caller)
+                unsafe { *nul_pos_out = pos };
+            }
+            // SAFETY: ownership of the Null variant passes to the caller
+            unsafe { FzString::Null.return_val() }
+        }
+        None => {
+            // SAFETY: bytes contains no interior NUL, as just verified by memchr
+            let cstring = CString::new(bytes).expect("no interior NUL (just checked)");
+            // SAFETY: ownership of this owned CString passes to the caller
+            unsafe { FzString::CString(cstring).return_val() }
+        }
+    }
+}
+
 #[ffizz_header::item]
 #[ffizz(order = 201)]
 /// Get the content of the string as a regular C string.
@@ -257,6 +307,53 @@ pub unsafe extern "C" fn tc_string_content_with_len(
     ::ffizz_string::fz_string_content_with_len(fzstr, len_out)
 }
 
+#[ffizz_header::item]
+#[ffizz(order = 202)]
+/// Get the content of the string as a regular C string, the same as `tc_string_content`, but
+/// substituting U+FFFD (the Unicode replacement character) for any invalid UTF-8, instead of
+/// returning NULL.  Interior NUL bytes, which cannot be represented in a C string, are treated
+/// as invalid and replaced the same way.  The Null variant is rendered as an empty string.
+///
+/// Unlike `tc_string_content`, this function never returns NULL, giving UI code a
+/// guaranteed-non-NULL rendering path for data of unknown provenance (such as a tag or
+/// description read from an external source).
+///
+/// This function takes the `TCString` by pointer because it caches the converted content
+/// in-place, the same way `tc_string_content` may modify the `TCString` to add a NUL
+/// terminator.  The pointer must not be NULL.
+///
+/// This function does _not_ take ownership of the TCString.
+///
+/// # Safety
+///
+/// The returned string is "borrowed" and remains valid only until the `TCString` is freed or
+/// passed to any other API function.
+///
+/// ```c
+/// EXTERN_C const char *tc_string_content_lossy(struct TCString *tcstring);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_content_lossy(
+    fzstr: *mut ::ffizz_string::fz_string_t,
+) -> *const i8 {
+    debug_assert!(!fzstr.is_null());
+    // SAFETY: fzstr is not NULL and points to a valid, owned TCString (promised by caller)
+    let s = unsafe { FzString::take(std::ptr::read(fzstr)) };
+    let mut lossy = String::from_utf8_lossy(s.as_bytes().unwrap_or(&[])).into_owned();
+    if lossy.contains('\0') {
+        lossy = lossy.replace('\0', "\u{fffd}");
+    }
+    let cstring = CString::new(lossy).expect("interior NULs were just replaced with U+FFFD");
+    let ptr = cstring.as_ptr();
+    // SAFETY:
+    //  - fzstr is not NULL (checked above) and points to valid, writable memory
+    //  - ownership of the cached CString is transferred to the TCString, so `ptr` (which points
+    //    into the CString's own heap allocation, not its stack slot) remains valid until fzstr
+    //    is next modified or freed
+    unsafe { FzString::initialize(fzstr, FzString::CString(cstring)) };
+    ptr
+}
+
 #[ffizz_header::item]
 #[ffizz(order = 201)]
 /// Determine whether the given `TCString` is a Null variant.
@@ -270,6 +367,58 @@ pub unsafe extern "C" fn tc_string_is_null(fzstr: *const ::ffizz_string::fz_stri
     ::ffizz_string::fz_string_is_null(fzstr)
 }
 
+#[ffizz_header::item]
+#[ffizz(order = 202)]
+/// Get the content of the string as a pointer and a length that includes the terminating NUL,
+/// analogous to `CStr::to_bytes_with_nul`.  Unlike `tc_string_content`, which may require a
+/// second call to find the length, this returns both in one call.  Unlike
+/// `tc_string_content_with_len`, the terminator is guaranteed present even for strings
+/// containing interior NULs or invalid UTF-8, so the returned buffer can be handed directly to
+/// length-aware APIs without a redundant `strlen`.
+///
+/// The Null variant is treated as an empty string.
+///
+/// This function takes the `TCString` by pointer because it caches the NUL-terminated content
+/// in-place, the same way `tc_string_content` may modify the `TCString`.  The pointer must not
+/// be NULL.
+///
+/// This function does _not_ take ownership of the TCString.
+///
+/// # Safety
+///
+/// The returned string is "borrowed" and remains valid only until the `TCString` is freed or
+/// passed to any other API function.
+///
+/// ```c
+/// EXTERN_C const char *tc_string_content_with_nul(struct TCString *tcstring, size_t *len_with_nul_out);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_content_with_nul(
+    fzstr: *mut ::ffizz_string::fz_string_t,
+    len_with_nul_out: *mut usize,
+) -> *const i8 {
+    debug_assert!(!fzstr.is_null());
+    // SAFETY: fzstr is not NULL and points to a valid, owned TCString (promised by caller)
+    let s = unsafe { FzString::take(std::ptr::read(fzstr)) };
+    let mut buf = s.as_bytes().unwrap_or(&[]).to_vec();
+    buf.push(0);
+    if !len_with_nul_out.is_null() {
+        // SAFETY: len_with_nul_out is a valid, properly aligned out-param (promised by caller)
+        unsafe { *len_with_nul_out = buf.len() };
+    }
+    let ptr = buf.as_ptr() as *const i8;
+    // The terminator stays in the Vec's allocation just past its length, so the stored content
+    // is unchanged by this call.
+    buf.truncate(buf.len() - 1);
+    // SAFETY:
+    //  - fzstr is not NULL (checked above) and points to valid, writable memory
+    //  - ownership of the cached buffer is transferred to the TCString, so `ptr` (which points
+    //    into the Vec's own heap allocation, not its stack slot) remains valid until fzstr is
+    //    next modified or freed
+    unsafe { FzString::initialize(fzstr, FzString::Bytes(buf)) };
+    ptr
+}
+
 #[ffizz_header::item]
 #[ffizz(order = 201)]
 /// Free a `TCString`.
@@ -288,6 +437,118 @@ pub unsafe extern "C" fn tc_string_free(fzstr: *mut ::ffizz_string::fz_string_t)
     ::ffizz_string::fz_string_free(fzstr)
 }
 
+#[ffizz_header::item]
+#[ffizz(order = 203)]
+/// Create a new `TCString` by transcoding the given UTF-16 buffer (for example, a Windows
+/// `wchar_t *`) to the UTF-8 `TCString` used throughout the rest of this API.  Invalid code
+/// units (such as an unpaired surrogate) are replaced with U+FFFD, the same as
+/// `String::from_utf16_lossy`; this function, like `tc_string_clone`, never fails.
+///
+/// # Safety
+///
+/// The given pointer must not be NULL and must be valid for `len` UTF-16 code units.
+///
+/// ```c
+/// EXTERN_C TCString tc_string_clone_utf16(const uint16_t *ptr, size_t len);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_clone_utf16(
+    ptr: *const u16,
+    len: usize,
+) -> ::ffizz_string::fz_string_t {
+    // SAFETY: ptr is valid for len u16 code units (promised by caller)
+    let units = unsafe { std::slice::from_raw_parts(ptr, len) };
+    let content = String::from_utf16_lossy(units);
+    // SAFETY: ownership of this owned String passes to the caller
+    unsafe { FzString::String(content.into()).return_val() }
+}
+
+#[ffizz_header::item]
+#[ffizz(order = 204)]
+/// Get the content of the string transcoded to UTF-16 (for example, for a Windows `wchar_t *`),
+/// following the same strict behavior as `tc_string_content`: if the string's content is not
+/// valid UTF-8, this returns NULL and sets `*len_out` to zero.  The Null variant is rendered as
+/// an empty, non-NULL buffer.
+///
+/// Unlike the other `tc_string_content_…` functions, the returned buffer is *not* borrowed from
+/// the `TCString` -- a `TCString` only ever stores its content as UTF-8, so there is no way to
+/// cache a UTF-16 rendering inside it.  Instead, ownership of the buffer passes to the caller,
+/// who must free it with `tc_string_utf16_free`.
+///
+/// This function does _not_ take ownership of the TCString.
+///
+/// # Safety
+///
+/// The given pointer must not be NULL.
+///
+/// ```c
+/// EXTERN_C uint16_t *tc_string_content_utf16(struct TCString *tcstring, size_t *len_out);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_content_utf16(
+    fzstr: *mut ::ffizz_string::fz_string_t,
+    len_out: *mut usize,
+) -> *mut u16 {
+    debug_assert!(!fzstr.is_null());
+    // SAFETY: fzstr is not NULL and points to a valid, owned TCString (promised by caller)
+    let s = unsafe { FzString::take(std::ptr::read(fzstr)) };
+    // The Null variant is handled explicitly here, rather than relying on whatever
+    // `FzString::Null.as_str()` happens to return, since that's a detail of a type this crate
+    // doesn't control.
+    let result = if matches!(s, FzString::Null) {
+        Ok(Vec::new().into_boxed_slice())
+    } else {
+        s.as_str().map(|text| {
+            text.encode_utf16()
+                .collect::<Vec<u16>>()
+                .into_boxed_slice()
+        })
+    };
+    // SAFETY: fzstr is not NULL (checked above) and points to valid, writable memory; this
+    // restores the TCString to its original, unmodified representation
+    unsafe { FzString::initialize(fzstr, s) };
+    match result {
+        Ok(buf) => {
+            let len = buf.len();
+            if !len_out.is_null() {
+                // SAFETY: len_out is a valid, properly aligned out-param (promised by caller)
+                unsafe { *len_out = len };
+            }
+            Box::into_raw(buf) as *mut u16
+        }
+        Err(_) => {
+            if !len_out.is_null() {
+                // SAFETY: len_out is a valid, properly aligned out-param (promised by caller)
+                unsafe { *len_out = 0 };
+            }
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[ffizz_header::item]
+#[ffizz(order = 205)]
+/// Free a UTF-16 buffer returned by `tc_string_content_utf16`.
+///
+/// # Safety
+///
+/// The buffer must have come from `tc_string_content_utf16`, with the same `len` that function
+/// wrote to `len_out`.  The buffer must not be used after this call, and must not be freed more
+/// than once.  It is safe to free a NULL buffer.
+///
+/// ```c
+/// EXTERN_C void tc_string_utf16_free(uint16_t *buf, size_t len);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_utf16_free(buf: *mut u16, len: usize) {
+    if buf.is_null() {
+        return;
+    }
+    // SAFETY: buf/len came from `Vec<u16>::into_boxed_slice` by way of `Box::into_raw`, so the
+    // capacity equals the length
+    drop(unsafe { Vec::from_raw_parts(buf, len, len) });
+}
+
 #[ffizz_header::item]
 #[ffizz(order = 211)]
 /// Free a TCStringList instance.  The instance, and all TCStringList it contains, must not be used after
@@ -327,6 +588,86 @@ pub unsafe extern "C" fn tc_string_list_free(tcstrings: *mut TCStringList) {
     drop(vec);
 }
 
+#[ffizz_header::item]
+#[ffizz(order = 212)]
+/// Create a new, empty TCStringList with the given initial capacity.  This, along with
+/// `tc_string_list_push`, allows a C caller to build a TCStringList incrementally instead of
+/// only receiving one as a return value.
+///
+/// The returned TCStringList must be freed, via tc_string_list_free or by passing it to a
+/// function which takes ownership of it, just like any other TCStringList.
+///
+/// ```c
+/// EXTERN_C struct TCStringList tc_string_list_new(size_t capacity);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_list_new(capacity: usize) -> TCStringList {
+    // SAFETY: caller takes ownership of the returned list and must free it
+    unsafe { TCStringList::return_val(Vec::with_capacity(capacity)) }
+}
+
+#[ffizz_header::item]
+#[ffizz(order = 213)]
+/// Push a TCString onto the end of a TCStringList, taking ownership of the pushed string and
+/// growing the list as necessary, like `Vec::push`.
+///
+/// # Safety
+///
+/// tcstrings must not be NULL and must point to a valid TCStringList.
+///
+/// string is moved into the list; the caller must not use or free it afterward.
+///
+/// ```c
+/// EXTERN_C void tc_string_list_push(struct TCStringList *tcstrings, struct TCString string);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_list_push(tcstrings: *mut TCStringList, string: TCString) {
+    debug_assert!(!tcstrings.is_null());
+    // SAFETY:
+    //  - satisfies the first case in from_raw_parts' safety documentation
+    let placeholder = unsafe { TCStringList::from_raw_parts(std::ptr::null_mut(), 0, 0) };
+    // SAFETY:
+    //  - *tcstrings is a valid TCStringList (promised by caller)
+    let mut vec = unsafe { TCStringList::take_val_from_arg(tcstrings, placeholder) };
+    // SAFETY: string is a valid, owned TCString (promised by caller)
+    vec.push(string);
+    // SAFETY: tcstrings is not NULL (checked above) and points to valid, writable memory
+    unsafe { std::ptr::write(tcstrings, TCStringList::return_val(vec)) };
+}
+
+#[ffizz_header::item]
+#[ffizz(order = 214)]
+/// Remove all strings from a TCStringList, freeing them, while keeping the list itself valid
+/// and usable (for example with further calls to tc_string_list_push).
+///
+/// # Safety
+///
+/// tcstrings must not be NULL and must point to a valid TCStringList.
+///
+/// ```c
+/// EXTERN_C void tc_string_list_clear(struct TCStringList *tcstrings);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn tc_string_list_clear(tcstrings: *mut TCStringList) {
+    debug_assert!(!tcstrings.is_null());
+    // SAFETY:
+    //  - satisfies the first case in from_raw_parts' safety documentation
+    let placeholder = unsafe { TCStringList::from_raw_parts(std::ptr::null_mut(), 0, 0) };
+    // SAFETY:
+    //  - *tcstrings is a valid TCStringList (promised by caller)
+    let mut vec = unsafe { TCStringList::take_val_from_arg(tcstrings, placeholder) };
+
+    for e in vec.drain(..) {
+        // SAFETY:
+        //  - e is a valid string (promised by caller)
+        //  - e is owned
+        drop(unsafe { FzString::take(e) });
+    }
+
+    // SAFETY: tcstrings is not NULL (checked above) and points to valid, writable memory
+    unsafe { std::ptr::write(tcstrings, TCStringList::return_val(vec)) };
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -350,6 +691,34 @@ mod test {
         assert_eq!(tcstrings.capacity, 0);
     }
 
+    #[test]
+    fn new_has_non_null_pointer() {
+        let mut tcstrings = unsafe { tc_string_list_new(2) };
+        assert!(!tcstrings.items.is_null());
+        assert_eq!(tcstrings.len, 0);
+        unsafe { tc_string_list_free(&mut tcstrings) };
+    }
+
+    #[test]
+    fn push_grows_the_list() {
+        let mut tcstrings = unsafe { tc_string_list_new(0) };
+        unsafe { tc_string_list_push(&mut tcstrings, tc_string_clone(c"a".as_ptr())) };
+        unsafe { tc_string_list_push(&mut tcstrings, tc_string_clone(c"b".as_ptr())) };
+        assert_eq!(tcstrings.len, 2);
+        unsafe { tc_string_list_free(&mut tcstrings) };
+    }
+
+    #[test]
+    fn clear_frees_strings_but_keeps_the_list_usable() {
+        let mut tcstrings = unsafe { tc_string_list_new(0) };
+        unsafe { tc_string_list_push(&mut tcstrings, tc_string_clone(c"a".as_ptr())) };
+        unsafe { tc_string_list_clear(&mut tcstrings) };
+        assert_eq!(tcstrings.len, 0);
+        unsafe { tc_string_list_push(&mut tcstrings, tc_string_clone(c"b".as_ptr())) };
+        assert_eq!(tcstrings.len, 1);
+        unsafe { tc_string_list_free(&mut tcstrings) };
+    }
+
     const INVALID_UTF8: &[u8] = b"abc\xf0\x28\x8c\x28";
 
     fn make_cstring() -> FzString<'static> {
@@ -410,27 +779,32 @@ mod test {
 
     #[test]
     fn cstring_as_bytes() {
-        assert_eq!(make_cstring().as_bytes(), b"a string");
+        assert_eq!(make_cstring().as_bytes().unwrap(), b"a string");
     }
 
     #[test]
     fn cstr_as_bytes() {
-        assert_eq!(make_cstr().as_bytes(), b"a string");
+        assert_eq!(make_cstr().as_bytes().unwrap(), b"a string");
     }
 
     #[test]
     fn string_as_bytes() {
-        assert_eq!(make_string().as_bytes(), b"a string");
+        assert_eq!(make_string().as_bytes().unwrap(), b"a string");
     }
 
     #[test]
     fn string_with_nul_as_bytes() {
-        assert_eq!(make_string_with_nul().as_bytes(), b"a \0 nul!");
+        assert_eq!(make_string_with_nul().as_bytes().unwrap(), b"a \0 nul!");
     }
 
     #[test]
     fn invalid_bytes_as_bytes() {
-        assert_eq!(make_invalid_bytes().as_bytes(), INVALID_UTF8);
+        assert_eq!(make_invalid_bytes().as_bytes().unwrap(), INVALID_UTF8);
+    }
+
+    #[test]
+    fn null_as_bytes() {
+        assert_eq!(FzString::Null.as_bytes(), None);
     }
 
     #[test]
@@ -467,4 +841,124 @@ mod test {
         tcstring.string_to_cstring();
         assert_eq!(tcstring, make_bytes()); // unchanged
     }
+
+    #[test]
+    fn clone_checked_accepts_nul_free_input() {
+        let mut nul_pos = usize::MAX;
+        let mut tcstring =
+            unsafe { tc_string_clone_checked(b"hello".as_ptr() as *const i8, 5, &mut nul_pos) };
+        assert!(!unsafe { tc_string_is_null(&tcstring) });
+        assert_eq!(nul_pos, usize::MAX); // untouched on success
+        unsafe { tc_string_free(&mut tcstring) };
+    }
+
+    #[test]
+    fn clone_checked_rejects_interior_nul() {
+        let mut nul_pos = usize::MAX;
+        let mut tcstring = unsafe {
+            tc_string_clone_checked(b"ab\0cd".as_ptr() as *const i8, 5, &mut nul_pos)
+        };
+        assert!(unsafe { tc_string_is_null(&tcstring) });
+        assert_eq!(nul_pos, 2);
+        unsafe { tc_string_free(&mut tcstring) };
+    }
+
+    #[test]
+    fn content_lossy_passes_through_valid_utf8() {
+        let mut tcstring = unsafe { make_string().return_val() };
+        let ptr = unsafe { tc_string_content_lossy(&mut tcstring) };
+        let content = unsafe { std::ffi::CStr::from_ptr(ptr) };
+        assert_eq!(content.to_str().unwrap(), "a string");
+    }
+
+    #[test]
+    fn content_lossy_substitutes_invalid_utf8() {
+        let mut tcstring = unsafe { make_invalid_bytes().return_val() };
+        let ptr = unsafe { tc_string_content_lossy(&mut tcstring) };
+        let content = unsafe { std::ffi::CStr::from_ptr(ptr) };
+        let content = content.to_str().unwrap();
+        assert!(content.starts_with("abc"));
+        assert!(content.contains('\u{fffd}'));
+    }
+
+    #[test]
+    fn content_lossy_never_returns_null() {
+        let mut tcstring = unsafe { FzString::Null.return_val() };
+        let ptr = unsafe { tc_string_content_lossy(&mut tcstring) };
+        assert!(!ptr.is_null());
+        let content = unsafe { std::ffi::CStr::from_ptr(ptr) };
+        assert_eq!(content.to_str().unwrap(), "");
+    }
+
+    #[test]
+    fn content_with_nul_reports_the_full_length() {
+        let mut tcstring = unsafe { make_string().return_val() };
+        let mut len_with_nul = 0;
+        let ptr = unsafe { tc_string_content_with_nul(&mut tcstring, &mut len_with_nul) };
+        assert_eq!(len_with_nul, "a string".len() + 1);
+        let content = unsafe { std::slice::from_raw_parts(ptr as *const u8, len_with_nul) };
+        assert_eq!(content, b"a string\0");
+    }
+
+    #[test]
+    fn content_with_nul_terminates_bytes_with_interior_nuls() {
+        let mut tcstring = unsafe { make_string_with_nul().return_val() };
+        let mut len_with_nul = 0;
+        let ptr = unsafe { tc_string_content_with_nul(&mut tcstring, &mut len_with_nul) };
+        assert_eq!(len_with_nul, "a \0 nul!".len() + 1);
+        let content = unsafe { std::slice::from_raw_parts(ptr as *const u8, len_with_nul) };
+        assert_eq!(content, b"a \0 nul!\0");
+    }
+
+    #[test]
+    fn content_with_nul_treats_null_as_empty() {
+        let mut tcstring = unsafe { FzString::Null.return_val() };
+        let mut len_with_nul = 0;
+        let ptr = unsafe { tc_string_content_with_nul(&mut tcstring, &mut len_with_nul) };
+        assert_eq!(len_with_nul, 1);
+        let content = unsafe { std::slice::from_raw_parts(ptr as *const u8, len_with_nul) };
+        assert_eq!(content, b"\0");
+    }
+
+    #[test]
+    fn clone_utf16_round_trips_through_content_utf16() {
+        let units: Vec<u16> = "a string".encode_utf16().collect();
+        let mut tcstring = unsafe { tc_string_clone_utf16(units.as_ptr(), units.len()) };
+        let mut len_out = 0;
+        let buf = unsafe { tc_string_content_utf16(&mut tcstring, &mut len_out) };
+        assert!(!buf.is_null());
+        let content = unsafe { std::slice::from_raw_parts(buf, len_out) };
+        assert_eq!(content, units.as_slice());
+        unsafe { tc_string_utf16_free(buf, len_out) };
+        unsafe { tc_string_free(&mut tcstring) };
+    }
+
+    #[test]
+    fn clone_utf16_substitutes_unpaired_surrogates() {
+        let units: Vec<u16> = vec![b'a' as u16, 0xd800, b'b' as u16]; // unpaired high surrogate
+        let mut tcstring = unsafe { tc_string_clone_utf16(units.as_ptr(), units.len()) };
+        let content = unsafe { FzString::take(tcstring) };
+        assert_eq!(content.as_str().unwrap(), "a\u{fffd}b");
+    }
+
+    #[test]
+    fn content_utf16_is_null_for_invalid_utf8() {
+        let mut tcstring = unsafe { make_invalid_bytes().return_val() };
+        let mut len_out = usize::MAX;
+        let buf = unsafe { tc_string_content_utf16(&mut tcstring, &mut len_out) };
+        assert!(buf.is_null());
+        assert_eq!(len_out, 0);
+        unsafe { tc_string_free(&mut tcstring) };
+    }
+
+    #[test]
+    fn content_utf16_treats_null_as_empty() {
+        let mut tcstring = unsafe { FzString::Null.return_val() };
+        let mut len_out = usize::MAX;
+        let buf = unsafe { tc_string_content_utf16(&mut tcstring, &mut len_out) };
+        assert!(!buf.is_null());
+        assert_eq!(len_out, 0);
+        unsafe { tc_string_utf16_free(buf, len_out) };
+        unsafe { tc_string_free(&mut tcstring) };
+    }
 }